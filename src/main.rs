@@ -1,25 +1,151 @@
+use arboard::Clipboard;
 use crossterm::{
     event::{
         self, DisableMouseCapture, EnableMouseCapture, KeyCode, KeyEventKind, KeyModifiers,
-        MouseEventKind,
+        MouseButton, MouseEventKind,
     },
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use ratatui::{prelude::*, widgets::*};
+use regex::Regex;
 use std::io;
 use std::sync::{Arc, Mutex};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
 struct App {
     show_popup: bool,
+    search: SearchState,
+    mode: Mode,
+    /// Start/end message indices of the current selection, in the order
+    /// they were chosen (not necessarily start <= end).
+    selection: Option<(usize, usize)>,
+    /// Whether `j`/`k`/`g`/`G` are currently extending `selection` rather
+    /// than just moving the viewport (toggled with `v`).
+    selecting: bool,
+    /// Index into `input.messages` that `j`/`k`/`g`/`G` move. Independent
+    /// of `scroll.vertical_scroll`, which is just the index of the
+    /// topmost visible row, so the view scrolls to follow this instead of
+    /// the other way around.
+    cursor: usize,
 }
+
+/// The keyboard layer `run_app` is currently dispatching to. Kept separate
+/// from `InputMode` (which only describes the Find popup) so the status
+/// line can show what the scrollback's motion keys will do.
+#[derive(PartialEq, Clone, Copy)]
+enum Mode {
+    Normal,
+    Editing,
+}
+
+impl Mode {
+    fn label(self) -> &'static str {
+        match self {
+            Mode::Normal => "NORMAL",
+            Mode::Editing => "EDITING",
+        }
+    }
+}
+
+/// A single match within `input.messages`: the index of the matched
+/// message and the byte range of the match within that message.
+type SearchMatch = (usize, usize, usize);
+
+/// Tracks the compiled "Find" query and the matches it produces against
+/// `input.messages`, so the list of matches only gets rebuilt when the
+/// query text actually changes.
 #[derive(Default)]
+struct SearchState {
+    query: String,
+    pattern: Option<Regex>,
+    matches: Vec<SearchMatch>,
+    current: usize,
+}
+
+impl SearchState {
+    /// Recompile and rescan only if `query` differs from the last one we
+    /// searched for; cheap no-op otherwise.
+    fn update(&mut self, query: &str, messages: &[String]) {
+        if query == self.query {
+            return;
+        }
+        self.query = query.to_string();
+        self.current = 0;
+        if query.is_empty() {
+            self.pattern = None;
+            self.matches.clear();
+            return;
+        }
+        let pattern = Regex::new(query).unwrap_or_else(|_| {
+            Regex::new(&regex::escape(query)).expect("escaped pattern is always valid")
+        });
+        self.matches = Self::scan(&pattern, messages);
+        self.pattern = Some(pattern);
+    }
+
+    /// Rescans `messages` against the already-compiled pattern, without
+    /// recompiling it; used when the message list changes rather than the
+    /// query itself (e.g. a new message is submitted).
+    fn rescan(&mut self, messages: &[String]) {
+        self.current = 0;
+        self.matches = match &self.pattern {
+            Some(pattern) => Self::scan(pattern, messages),
+            None => Vec::new(),
+        };
+    }
+
+    /// Finds every match of `pattern` across `messages`, in message order.
+    fn scan(pattern: &Regex, messages: &[String]) -> Vec<SearchMatch> {
+        let mut matches = Vec::new();
+        for (i, message) in messages.iter().enumerate() {
+            for m in pattern.find_iter(message) {
+                matches.push((i, m.start(), m.end()));
+            }
+        }
+        matches
+    }
+
+    fn next_match(&mut self) {
+        if !self.matches.is_empty() {
+            self.current = (self.current + 1) % self.matches.len();
+        }
+    }
+
+    fn prev_match(&mut self) {
+        if !self.matches.is_empty() {
+            self.current = (self.current + self.matches.len() - 1) % self.matches.len();
+        }
+    }
+}
+
 struct ScrollState {
     pub vertical_scroll: usize,
+    /// Whether the view should jump to the bottom as new messages arrive,
+    /// like a log tailer. Disabled by any manual scroll away from the
+    /// bottom, re-enabled by scrolling back to it (or `G`).
+    pub follow: bool,
+}
+
+impl Default for ScrollState {
+    fn default() -> Self {
+        Self {
+            vertical_scroll: 0,
+            follow: true,
+        }
+    }
 }
 
 impl App {
     fn new() -> App {
-        App { show_popup: false }
+        App {
+            show_popup: false,
+            search: SearchState::default(),
+            mode: Mode::Normal,
+            selection: None,
+            selecting: false,
+            cursor: 0,
+        }
     }
 }
 #[derive(PartialEq)]
@@ -33,6 +159,8 @@ struct Input {
     cursor_position: usize,
     input_mode: InputMode,
     messages: Vec<String>,
+    history: Vec<String>,
+    history_pos: Option<usize>,
 }
 
 impl Default for Input {
@@ -42,11 +170,33 @@ impl Default for Input {
             input_mode: InputMode::Normal,
             messages: Vec::new(),
             cursor_position: 0,
+            history: Vec::new(),
+            history_pos: None,
         }
     }
 }
 
 impl Input {
+    /// Byte offset in `self.input` of the grapheme at `cursor_position`,
+    /// i.e. where an insert/delete at the cursor should actually happen.
+    fn byte_index(&self) -> usize {
+        self.input
+            .grapheme_indices(true)
+            .nth(self.cursor_position)
+            .map(|(i, _)| i)
+            .unwrap_or(self.input.len())
+    }
+
+    /// Number of terminal columns the graphemes before the cursor occupy,
+    /// accounting for double-width (CJK/emoji) characters.
+    fn cursor_column(&self) -> u16 {
+        self.input
+            .graphemes(true)
+            .take(self.cursor_position)
+            .map(UnicodeWidthStr::width)
+            .sum::<usize>() as u16
+    }
+
     fn move_cursor_left(&mut self) {
         let cursor_moved_left = self.cursor_position.saturating_sub(1);
         self.cursor_position = self.clamp_cursor(cursor_moved_left);
@@ -58,7 +208,9 @@ impl Input {
     }
 
     fn enter_char(&mut self, new_char: char) {
-        self.input.insert(self.cursor_position, new_char);
+        let byte_index = self.byte_index();
+        self.input.insert(byte_index, new_char);
+        self.history_pos = None;
 
         self.move_cursor_right();
     }
@@ -68,15 +220,49 @@ impl Input {
         if is_not_cursor_leftmost {
             let current_index = self.cursor_position;
             let from_left_to_current_index = current_index - 1;
-            let before_char_to_delete = self.input.chars().take(from_left_to_current_index);
-            let after_char_to_delete = self.input.chars().skip(current_index);
+            let before_char_to_delete = self.input.graphemes(true).take(from_left_to_current_index);
+            let after_char_to_delete = self.input.graphemes(true).skip(current_index);
             self.input = before_char_to_delete.chain(after_char_to_delete).collect();
+            self.history_pos = None;
             self.move_cursor_left();
         }
     }
 
+    /// Loads the previous history entry into `input`, walking backward
+    /// from the most recent submission on the first press.
+    fn history_prev(&mut self) {
+        if self.history.is_empty() {
+            return;
+        }
+        let new_pos = match self.history_pos {
+            None => self.history.len() - 1,
+            Some(0) => 0,
+            Some(pos) => pos - 1,
+        };
+        self.history_pos = Some(new_pos);
+        self.input = self.history[new_pos].clone();
+        self.cursor_position = self.input.graphemes(true).count();
+    }
+
+    /// Loads the next, more recent history entry, or clears back to a
+    /// blank line once the newest entry is passed.
+    fn history_next(&mut self) {
+        match self.history_pos {
+            Some(pos) if pos + 1 < self.history.len() => {
+                self.history_pos = Some(pos + 1);
+                self.input = self.history[pos + 1].clone();
+            }
+            Some(_) => {
+                self.history_pos = None;
+                self.input.clear();
+            }
+            None => return,
+        }
+        self.cursor_position = self.input.graphemes(true).count();
+    }
+
     fn clamp_cursor(&self, new_cursor_pos: usize) -> usize {
-        new_cursor_pos.clamp(0, self.input.len())
+        new_cursor_pos.clamp(0, self.input.graphemes(true).count())
     }
 
     fn reset_cursor(&mut self) {
@@ -84,6 +270,8 @@ impl Input {
     }
 
     fn submit_message(&mut self) {
+        self.history.push(self.input.clone());
+        self.history_pos = None;
         self.messages.push(self.input.clone());
         self.input.clear();
         self.reset_cursor();
@@ -122,26 +310,41 @@ fn run_app<B: Backend>(
 ) -> io::Result<()> {
     loop {
         let terminal_size = terminal.size()?; // Get the terminal size
+        // The scrollback list's actual on-screen height, 2 rows short of
+        // `terminal_size.height` because of `ui`'s `Layout::margin(1)`; all
+        // scroll/follow math below must use this, not the raw terminal size.
+        let list_height = list_pane_rect(terminal_size).height;
         terminal.draw(|f| ui(f, &app, &input, &mut scroll.lock().unwrap()))?;
         if let event::Event::Key(key) = event::read()? {
             if key.kind == KeyEventKind::Press {
                 let ctrl_pressed = key.modifiers.contains(KeyModifiers::CONTROL);
                 match key.code {
-                    KeyCode::Char('q') => {
+                    KeyCode::Char('q') if app.mode == Mode::Normal => {
                         return Ok(());
                     }
                     KeyCode::Up => {
-                        let mut scroll = scroll.lock().unwrap(); // Lock the Mutex
-                        if scroll.vertical_scroll > 0 {
-                            scroll.vertical_scroll = scroll.vertical_scroll.saturating_sub(1);
+                        if input.input_mode == InputMode::Editing {
+                            input.history_prev();
+                        } else {
+                            let mut scroll = scroll.lock().unwrap(); // Lock the Mutex
+                            if scroll.vertical_scroll > 0 {
+                                scroll.vertical_scroll = scroll.vertical_scroll.saturating_sub(1);
+                            }
+                            sync_follow(&mut scroll, input.messages.len(), list_height);
                         }
                     }
                     KeyCode::Down => {
-                        let mut scroll = scroll.lock().unwrap();
-                        let max_scroll = (input.messages.len() as i32 - terminal_size.height as i32)
-                            .max(0) as usize;
-                        if scroll.vertical_scroll < max_scroll {
-                            scroll.vertical_scroll += 1;
+                        if input.input_mode == InputMode::Editing {
+                            input.history_next();
+                        } else {
+                            let mut scroll = scroll.lock().unwrap();
+                            let max_scroll = (input.messages.len() as i32
+                                - list_height as i32)
+                                .max(0) as usize;
+                            if scroll.vertical_scroll < max_scroll {
+                                scroll.vertical_scroll += 1;
+                            }
+                            sync_follow(&mut scroll, input.messages.len(), list_height);
                         }
                     }
 
@@ -150,25 +353,130 @@ fn run_app<B: Backend>(
                         match input.input_mode {
                             InputMode::Normal => {
                                 input.input_mode = InputMode::Editing;
+                                app.mode = Mode::Editing;
                             }
                             InputMode::Editing => {
                                 input.input_mode = InputMode::Normal;
+                                app.mode = Mode::Normal;
                             }
                         }
                     }
+                    KeyCode::Char('j') if app.mode == Mode::Normal => {
+                        let last = input.messages.len().saturating_sub(1);
+                        app.cursor = (app.cursor + 1).min(last);
+                        {
+                            let mut scroll = scroll.lock().unwrap();
+                            bring_into_view(&mut scroll, app.cursor, list_height);
+                            sync_follow(&mut scroll, input.messages.len(), list_height);
+                        }
+                        let cursor = app.cursor;
+                        extend_selection(&mut app, cursor);
+                    }
+                    KeyCode::Char('k') if app.mode == Mode::Normal => {
+                        app.cursor = app.cursor.saturating_sub(1);
+                        {
+                            let mut scroll = scroll.lock().unwrap();
+                            bring_into_view(&mut scroll, app.cursor, list_height);
+                            sync_follow(&mut scroll, input.messages.len(), list_height);
+                        }
+                        let cursor = app.cursor;
+                        extend_selection(&mut app, cursor);
+                    }
+                    KeyCode::Char('d') if ctrl_pressed && app.mode == Mode::Normal => {
+                        let last = input.messages.len().saturating_sub(1);
+                        let half = (list_height / 2) as usize;
+                        app.cursor = (app.cursor + half).min(last);
+                        {
+                            let mut scroll = scroll.lock().unwrap();
+                            bring_into_view(&mut scroll, app.cursor, list_height);
+                            sync_follow(&mut scroll, input.messages.len(), list_height);
+                        }
+                        let cursor = app.cursor;
+                        extend_selection(&mut app, cursor);
+                    }
+                    KeyCode::Char('u') if ctrl_pressed && app.mode == Mode::Normal => {
+                        let half = (list_height / 2) as usize;
+                        app.cursor = app.cursor.saturating_sub(half);
+                        {
+                            let mut scroll = scroll.lock().unwrap();
+                            bring_into_view(&mut scroll, app.cursor, list_height);
+                            sync_follow(&mut scroll, input.messages.len(), list_height);
+                        }
+                        let cursor = app.cursor;
+                        extend_selection(&mut app, cursor);
+                    }
+                    KeyCode::Char('g') if app.mode == Mode::Normal => {
+                        app.cursor = 0;
+                        let mut scroll = scroll.lock().unwrap();
+                        scroll.vertical_scroll = 0;
+                        sync_follow(&mut scroll, input.messages.len(), list_height);
+                        drop(scroll);
+                        extend_selection(&mut app, 0);
+                    }
+                    KeyCode::Char('G') if app.mode == Mode::Normal => {
+                        app.cursor = input.messages.len().saturating_sub(1);
+                        {
+                            let mut scroll = scroll.lock().unwrap();
+                            let max_scroll = (input.messages.len() as i32
+                                - list_height as i32)
+                                .max(0) as usize;
+                            scroll.vertical_scroll = max_scroll;
+                            scroll.follow = true;
+                        }
+                        let cursor = app.cursor;
+                        extend_selection(&mut app, cursor);
+                    }
+                    KeyCode::Char('v') if app.mode == Mode::Normal => {
+                        app.selecting = !app.selecting;
+                        if app.selecting {
+                            app.selection = Some((app.cursor, app.cursor));
+                        }
+                    }
+                    KeyCode::Char('y') if app.mode == Mode::Normal => {
+                        copy_selection_to_clipboard(&app, &input);
+                    }
+                    KeyCode::Char('c') if ctrl_pressed && app.mode == Mode::Normal => {
+                        copy_selection_to_clipboard(&app, &input);
+                    }
                     KeyCode::Enter => {
                         if input.input_mode == InputMode::Editing {
                             input.submit_message();
+                            app.search.rescan(&input.messages);
+                            let mut scroll = scroll.lock().unwrap();
+                            if scroll.follow {
+                                let max_scroll = (input.messages.len() as i32
+                                    - list_height as i32)
+                                    .max(0) as usize;
+                                scroll.vertical_scroll = max_scroll;
+                            }
+                        }
+                    }
+                    KeyCode::Char('n') if !ctrl_pressed && app.mode == Mode::Normal => {
+                        app.search.next_match();
+                        if let Some(&(msg_idx, ..)) = app.search.matches.get(app.search.current) {
+                            let mut scroll = scroll.lock().unwrap();
+                            bring_into_view(&mut scroll, msg_idx, list_height);
+                            sync_follow(&mut scroll, input.messages.len(), list_height);
+                        }
+                    }
+                    KeyCode::Char('N') if app.mode == Mode::Normal => {
+                        app.search.prev_match();
+                        if let Some(&(msg_idx, ..)) = app.search.matches.get(app.search.current) {
+                            let mut scroll = scroll.lock().unwrap();
+                            bring_into_view(&mut scroll, msg_idx, list_height);
+                            sync_follow(&mut scroll, input.messages.len(), list_height);
                         }
                     }
                     KeyCode::Char(to_insert) => {
                         if input.input_mode == InputMode::Editing {
                             input.enter_char(to_insert);
+                            app.search.update(&input.input, &input.messages);
                         }
                     }
                     KeyCode::Backspace => {
                         if input.input_mode == InputMode::Editing {
                             input.delete_char();
+                            app.search.update(&input.input, &input.messages);
                         }
                     }
                     KeyCode::Left => {
@@ -190,16 +498,56 @@ fn run_app<B: Backend>(
                 MouseEventKind::ScrollDown => {
                     let mut scroll = scroll.lock().unwrap();
                     let max_scroll =
-                        (input.messages.len() as i32 - terminal_size.height as i32).max(0) as usize;
+                        (input.messages.len() as i32 - list_height as i32).max(0) as usize;
                     if scroll.vertical_scroll < max_scroll {
                         scroll.vertical_scroll += 1;
                     }
+                    sync_follow(&mut scroll, input.messages.len(), list_height);
                 }
                 MouseEventKind::ScrollUp => {
                     let mut scroll = scroll.lock().unwrap();
                     if scroll.vertical_scroll > 0 {
                         scroll.vertical_scroll -= 1;
                     }
+                    sync_follow(&mut scroll, input.messages.len(), list_height);
+                }
+                MouseEventKind::Down(MouseButton::Left) => {
+                    let scroll_offset = scroll.lock().unwrap().vertical_scroll;
+                    if let Some(idx) = mouse_row_to_message_index(
+                        mouse_event.column,
+                        mouse_event.row,
+                        terminal_size,
+                        scroll_offset,
+                        input.messages.len(),
+                    ) {
+                        app.selection = Some((idx, idx));
+                    }
+                }
+                MouseEventKind::Drag(MouseButton::Left) => {
+                    let scroll_offset = scroll.lock().unwrap().vertical_scroll;
+                    let idx = mouse_row_to_message_index(
+                        mouse_event.column,
+                        mouse_event.row,
+                        terminal_size,
+                        scroll_offset,
+                        input.messages.len(),
+                    );
+                    if let (Some(idx), Some((start, _))) = (idx, app.selection) {
+                        app.selection = Some((start, idx));
+                    }
+                }
+                MouseEventKind::Up(MouseButton::Left) => {
+                    let scroll_offset = scroll.lock().unwrap().vertical_scroll;
+                    let idx = mouse_row_to_message_index(
+                        mouse_event.column,
+                        mouse_event.row,
+                        terminal_size,
+                        scroll_offset,
+                        input.messages.len(),
+                    );
+                    if let (Some(idx), Some((start, _))) = (idx, app.selection) {
+                        app.selection = Some((start, idx));
+                    }
                 }
                 _ => {}
             }
@@ -213,21 +561,40 @@ fn ui<B: Backend>(f: &mut Frame<B>, app: &App, input: &Input, scroll: &mut Scrol
         .margin(1)
         .constraints([Constraint::Percentage(20), Constraint::Percentage(80)].as_ref())
         .split(size);
-    let block = Block::default().borders(Borders::ALL);
-    f.render_widget(block, chunks[0]);
+    let mut status_lines = vec![Line::from(app.mode.label())];
+    if scroll.follow {
+        status_lines.push(Line::from(Span::styled(
+            "FOLLOW",
+            Style::default().fg(Color::Green),
+        )));
+    }
+    let status = Paragraph::new(status_lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("Mode")
+            .title_alignment(Alignment::Center),
+    );
+    f.render_widget(status, chunks[0]);
     let block = Block::default().borders(Borders::ALL);
     f.render_widget(block, chunks[1]);
     let max_scroll = (input.messages.len() as i32 - chunks[1].height as i32).max(0) as usize;
     let clamped_scroll = scroll.vertical_scroll.min(max_scroll); // Ensure not exceeding max_scroll
+    let current_match = app.search.matches.get(app.search.current).copied();
+    let selected_range = app.selection.map(|(start, end)| (start.min(end), start.max(end)));
     let visible_messages: Vec<ListItem> = input
         .messages
         .iter()
+        .enumerate()
         .skip(clamped_scroll)
         .take(chunks[1].height as usize)
-        .enumerate()
-        .map(|(_, m)| {
-            let content = Line::from(Span::raw(m));
-            ListItem::new(content)
+        .map(|(i, m)| {
+            let item = ListItem::new(highlighted_line(m, i, &app.search.matches, current_match));
+            match selected_range {
+                Some((lo, hi)) if i >= lo && i <= hi => {
+                    item.style(Style::default().add_modifier(Modifier::REVERSED))
+                }
+                _ => item,
+            }
         })
         .collect();
     let message_list = List::new(visible_messages)
@@ -252,7 +619,7 @@ fn ui<B: Backend>(f: &mut Frame<B>, app: &App, input: &Input, scroll: &mut Scrol
             InputMode::Normal => {}
 
             InputMode::Editing => {
-                f.set_cursor(area.x + input.cursor_position as u16 + 1, area.y + 1)
+                f.set_cursor(area.x + input.cursor_column() + 1, area.y + 1)
             }
         }
 
@@ -261,6 +628,115 @@ fn ui<B: Backend>(f: &mut Frame<B>, app: &App, input: &Input, scroll: &mut Scrol
     }
 }
 
+/// Splits `message` into styled spans so that the byte ranges recorded in
+/// `matches` for this `msg_idx` render with a highlight, with the current
+/// match (if it belongs to this message) styled distinctly from the rest.
+fn highlighted_line<'a>(
+    message: &'a str,
+    msg_idx: usize,
+    matches: &[SearchMatch],
+    current_match: Option<SearchMatch>,
+) -> Line<'a> {
+    let highlight = Style::default().bg(Color::Yellow).fg(Color::Black);
+    let current = Style::default().bg(Color::Red).fg(Color::Black);
+
+    let mut spans = Vec::new();
+    let mut cursor = 0;
+    for &(i, start, end) in matches.iter().filter(|(i, ..)| *i == msg_idx) {
+        if start > cursor {
+            spans.push(Span::raw(&message[cursor..start]));
+        }
+        let style = if current_match == Some((i, start, end)) {
+            current
+        } else {
+            highlight
+        };
+        spans.push(Span::styled(&message[start..end], style));
+        cursor = end;
+    }
+    if cursor < message.len() {
+        spans.push(Span::raw(&message[cursor..]));
+    }
+    Line::from(spans)
+}
+
+/// Re-derives `follow` from the current scroll position: on once it's at
+/// (or past) the bottom, off as soon as it isn't.
+fn sync_follow(scroll: &mut ScrollState, messages_len: usize, window_height: u16) {
+    let max_scroll = (messages_len as i32 - window_height as i32).max(0) as usize;
+    scroll.follow = scroll.vertical_scroll >= max_scroll;
+}
+
+/// Extends the active selection to `current` if keyboard selection mode
+/// (`v`) is on; starts the selection at `current` if none exists yet.
+fn extend_selection(app: &mut App, current: usize) {
+    if app.selecting {
+        let start = app.selection.map(|(start, _)| start).unwrap_or(current);
+        app.selection = Some((start, current));
+    }
+}
+
+/// The scrollback list's on-screen rect, matching the layout `ui` draws
+/// it into (`Layout::margin(1)` plus the 20/80 horizontal split).
+fn list_pane_rect(terminal_size: Rect) -> Rect {
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .margin(1)
+        .constraints([Constraint::Percentage(20), Constraint::Percentage(80)].as_ref())
+        .split(terminal_size);
+    chunks[1]
+}
+
+/// Maps a mouse click/drag at `(column, row)` to a message index, using
+/// the same chunk layout `ui` draws the scrollback list into. Returns
+/// `None` past the end of `input.messages` rather than an out-of-range
+/// index, since fewer messages than terminal rows is the common case.
+fn mouse_row_to_message_index(
+    column: u16,
+    row: u16,
+    terminal_size: Rect,
+    scroll_offset: usize,
+    messages_len: usize,
+) -> Option<usize> {
+    let list_area = list_pane_rect(terminal_size);
+    if column < list_area.x || column >= list_area.x + list_area.width {
+        return None;
+    }
+    if row <= list_area.y || row >= list_area.y + list_area.height.saturating_sub(1) {
+        return None;
+    }
+    let idx = scroll_offset + (row - list_area.y - 1) as usize;
+    if idx >= messages_len {
+        return None;
+    }
+    Some(idx)
+}
+
+/// Joins the selected messages and writes them to the system clipboard.
+fn copy_selection_to_clipboard(app: &App, input: &Input) {
+    let Some((start, end)) = app.selection else {
+        return;
+    };
+    let (lo, hi) = (start.min(end), start.max(end).min(input.messages.len().saturating_sub(1)));
+    if input.messages.is_empty() {
+        return;
+    }
+    let text = input.messages[lo..=hi].join("\n");
+    if let Ok(mut clipboard) = Clipboard::new() {
+        let _ = clipboard.set_text(text);
+    }
+}
+
+/// Leaves the scroll alone when `msg_idx` is already inside the window of
+/// `window_height` lines; otherwise scrolls so it sits in the middle of
+/// that window (or as near as the top of the list allows).
+fn bring_into_view(scroll: &mut ScrollState, msg_idx: usize, window_height: u16) {
+    let window_height = window_height as usize;
+    if msg_idx < scroll.vertical_scroll || msg_idx >= scroll.vertical_scroll + window_height {
+        scroll.vertical_scroll = msg_idx.saturating_sub(window_height / 2);
+    }
+}
+
 fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
     let popup_layout = Layout::default()
         .direction(Direction::Vertical)
@@ -286,3 +762,232 @@ fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
         )
         .split(popup_layout[1])[1]
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn search_state_compiles_regex_and_finds_matches() {
+        let messages = vec!["hello world".to_string(), "goodbye world".to_string()];
+        let mut search = SearchState::default();
+        search.update("wor.d", &messages);
+        assert_eq!(search.matches, vec![(0, 6, 11), (1, 8, 13)]);
+    }
+
+    #[test]
+    fn search_state_falls_back_to_literal_on_invalid_regex() {
+        let messages = vec!["x[y] z".to_string()];
+        let mut search = SearchState::default();
+        search.update("[", &messages);
+        assert_eq!(search.matches, vec![(0, 1, 2)]);
+    }
+
+    #[test]
+    fn search_state_next_and_prev_match_wrap_around() {
+        let messages = vec!["a a a".to_string()];
+        let mut search = SearchState::default();
+        search.update("a", &messages);
+        assert_eq!(search.matches.len(), 3);
+        assert_eq!(search.current, 0);
+        search.next_match();
+        assert_eq!(search.current, 1);
+        search.prev_match();
+        search.prev_match();
+        assert_eq!(search.current, 2);
+    }
+
+    #[test]
+    fn search_state_rescan_reuses_compiled_pattern_against_new_messages() {
+        let mut search = SearchState::default();
+        search.update("a", &[]);
+        assert!(search.matches.is_empty());
+        search.rescan(&["a a".to_string()]);
+        assert_eq!(search.matches, vec![(0, 0, 1), (0, 2, 3)]);
+        // The query is untouched by rescan, so a no-op update still sees it
+        // as unchanged rather than recompiling.
+        assert_eq!(search.query, "a");
+    }
+
+    #[test]
+    fn extend_selection_is_a_no_op_when_not_selecting() {
+        let mut app = App::new();
+        extend_selection(&mut app, 5);
+        assert_eq!(app.selection, None);
+    }
+
+    #[test]
+    fn extend_selection_starts_at_current_then_extends_in_either_direction() {
+        let mut app = App::new();
+        app.selecting = true;
+        extend_selection(&mut app, 3);
+        assert_eq!(app.selection, Some((3, 3)));
+        extend_selection(&mut app, 6);
+        assert_eq!(app.selection, Some((3, 6)));
+        extend_selection(&mut app, 1);
+        assert_eq!(app.selection, Some((3, 1)));
+    }
+
+    #[test]
+    fn input_cursor_column_accounts_for_wide_graphemes() {
+        let mut input = Input::default();
+        for c in ['a', '你', 'b'] {
+            input.enter_char(c);
+        }
+        assert_eq!(input.input, "a你b");
+        assert_eq!(input.cursor_position, 3);
+        input.move_cursor_left();
+        assert_eq!(input.cursor_column(), 3); // 'a' (1 col) + '你' (2 cols)
+    }
+
+    #[test]
+    fn input_byte_index_finds_correct_offset_for_multibyte_text() {
+        let input = Input {
+            input: "a你b".to_string(),
+            cursor_position: 2,
+            ..Input::default()
+        };
+        assert_eq!(input.byte_index(), 1 + '你'.len_utf8());
+    }
+
+    #[test]
+    fn input_delete_char_removes_correct_grapheme_with_multibyte_text() {
+        let mut input = Input::default();
+        for c in ['a', '你', 'b'] {
+            input.enter_char(c);
+        }
+        input.delete_char();
+        assert_eq!(input.input, "a你");
+        assert_eq!(input.cursor_position, 2);
+    }
+
+    #[test]
+    fn history_prev_is_a_no_op_with_empty_history() {
+        let mut input = Input::default();
+        input.history_prev();
+        assert_eq!(input.history_pos, None);
+        assert_eq!(input.input, "");
+    }
+
+    #[test]
+    fn history_prev_walks_backward_and_stops_at_the_oldest_entry() {
+        let mut input = Input {
+            history: vec!["first".to_string(), "second".to_string()],
+            ..Input::default()
+        };
+        input.history_prev();
+        assert_eq!(input.history_pos, Some(1));
+        assert_eq!(input.input, "second");
+        assert_eq!(input.cursor_position, "second".graphemes(true).count());
+
+        input.history_prev();
+        assert_eq!(input.history_pos, Some(0));
+        assert_eq!(input.input, "first");
+
+        // Already at the oldest entry: stays put rather than wrapping.
+        input.history_prev();
+        assert_eq!(input.history_pos, Some(0));
+        assert_eq!(input.input, "first");
+    }
+
+    #[test]
+    fn history_next_walks_forward_then_clears_past_the_newest_entry() {
+        let mut input = Input {
+            history: vec!["first".to_string(), "second".to_string()],
+            history_pos: Some(0),
+            ..Input::default()
+        };
+        input.history_next();
+        assert_eq!(input.history_pos, Some(1));
+        assert_eq!(input.input, "second");
+
+        input.history_next();
+        assert_eq!(input.history_pos, None);
+        assert_eq!(input.input, "");
+    }
+
+    #[test]
+    fn history_next_is_a_no_op_when_not_navigating_history() {
+        let mut input = Input::default();
+        input.history_next();
+        assert_eq!(input.history_pos, None);
+        assert_eq!(input.input, "");
+    }
+
+    #[test]
+    fn enter_char_cancels_an_in_progress_history_walk() {
+        let mut input = Input {
+            history: vec!["first".to_string()],
+            ..Input::default()
+        };
+        input.history_prev();
+        assert_eq!(input.history_pos, Some(0));
+        input.enter_char('!');
+        assert_eq!(input.history_pos, None);
+        assert_eq!(input.input, "first!");
+    }
+
+    #[test]
+    fn mouse_row_to_message_index_rejects_rows_past_last_message() {
+        let terminal_size = Rect {
+            x: 0,
+            y: 0,
+            width: 40,
+            height: 10,
+        };
+        let list_area = list_pane_rect(terminal_size);
+        // Only one message; a click well below it should select nothing.
+        let far_row = list_area.y + list_area.height - 2;
+        assert!(mouse_row_to_message_index(list_area.x, far_row, terminal_size, 0, 1).is_none());
+    }
+
+    #[test]
+    fn mouse_row_to_message_index_accepts_row_within_range() {
+        let terminal_size = Rect {
+            x: 0,
+            y: 0,
+            width: 40,
+            height: 10,
+        };
+        let list_area = list_pane_rect(terminal_size);
+        let row = list_area.y + 1;
+        assert_eq!(
+            mouse_row_to_message_index(list_area.x, row, terminal_size, 0, 5),
+            Some(0)
+        );
+    }
+
+    #[test]
+    fn mouse_row_to_message_index_rejects_clicks_outside_list_column() {
+        let terminal_size = Rect {
+            x: 0,
+            y: 0,
+            width: 40,
+            height: 10,
+        };
+        let list_area = list_pane_rect(terminal_size);
+        assert!(mouse_row_to_message_index(0, list_area.y + 1, terminal_size, 0, 5).is_none());
+    }
+
+    #[test]
+    fn sync_follow_enables_at_bottom_and_disables_above_it() {
+        // messages_len=20, window_height=10 => max_scroll=10
+        let mut scroll = ScrollState {
+            vertical_scroll: 10,
+            ..ScrollState::default()
+        };
+        sync_follow(&mut scroll, 20, 10);
+        assert!(scroll.follow);
+
+        scroll.vertical_scroll = 5;
+        sync_follow(&mut scroll, 20, 10);
+        assert!(!scroll.follow);
+    }
+
+    #[test]
+    fn sync_follow_stays_true_when_all_messages_fit_on_screen() {
+        let mut scroll = ScrollState::default();
+        sync_follow(&mut scroll, 3, 10);
+        assert!(scroll.follow);
+    }
+}